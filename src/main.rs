@@ -1,15 +1,31 @@
 use anyhow::{anyhow, Context, Result};
+use async_compression::tokio::bufread::GzipDecoder;
+use async_compression::tokio::write::GzipEncoder;
+use axum::extract::{Path, Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::Router;
 use clap::{Parser, Subcommand};
 use csv::Writer;
+use futures::stream::Stream;
 use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::convert::Infallible;
 use std::ffi::OsStr;
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tower_http::compression::CompressionLayer;
+use tower_http::cors::CorsLayer;
 use surrealdb::engine::remote::http::{Client, Http};
 use surrealdb::opt::auth::Root;
 use surrealdb::{sql, Surreal};
 use tokio::fs;
-use tokio::io::AsyncWriteExt;
+use tokio::io::{AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
 use tokio::task::JoinSet;
 
 const ENDPOINT: &str = "127.0.0.1:8000";
@@ -18,6 +34,9 @@ const PASS: &str = "root";
 const NS: &str = "test";
 const DB: &str = "test";
 const FILEFORMAT: &str = "json";
+const BATCH_SIZE: usize = 1000;
+const BIND: &str = "127.0.0.1:3000";
+const RENAME_FIELDS: &str = "none";
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
@@ -43,6 +62,12 @@ struct Import {
     #[arg(long, default_value = DB)]
     db: String,
 
+    #[arg(long, default_value = RENAME_FIELDS)]
+    rename_fields: String,
+
+    #[arg(long)]
+    preserve_ids: bool,
+
     #[arg(value_name = "files", value_parser = clap::value_parser!(PathBuf), num_args = 1.., required = true)]
     files: Vec<PathBuf>,
 }
@@ -67,14 +92,51 @@ struct Export {
     #[arg(short, long, default_value = FILEFORMAT)]
     format: String,
 
+    #[arg(long, default_value_t = BATCH_SIZE)]
+    batch_size: usize,
+
+    #[arg(long, visible_alias = "array")]
+    pretty: bool,
+
+    #[arg(long)]
+    compress: bool,
+
+    #[arg(long, default_value = RENAME_FIELDS)]
+    rename_fields: String,
+
+    #[arg(long)]
+    preserve_ids: bool,
+
     #[arg(value_name = "tables", value_parser = clap::value_parser!(String), num_args = 1.., required = true)]
     tables: Vec<String>,
 }
 
+#[derive(Parser)]
+struct Serve {
+    #[arg(short, long, default_value = ENDPOINT )]
+    endpoint: String,
+
+    #[arg(short, long, default_value = USER)]
+    user: String,
+
+    #[arg(short, long, default_value = PASS)]
+    pass: String,
+
+    #[arg(long, default_value = NS)]
+    ns: String,
+
+    #[arg(long, default_value = DB)]
+    db: String,
+
+    #[arg(short, long, default_value = BIND)]
+    bind: String,
+}
+
 #[derive(Subcommand)]
 enum Commands {
     Import(Import),
     Export(Export),
+    Serve(Serve),
 }
 
 // defaults are for my own sanity
@@ -87,6 +149,26 @@ enum FileFormat {
     Csv,
 }
 
+#[derive(Clone, Copy)]
+enum KeyCase {
+    Snake,
+    Camel,
+    None,
+}
+
+impl FromStr for KeyCase {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "snake" => Ok(KeyCase::Snake),
+            "camel" => Ok(KeyCase::Camel),
+            "none" => Ok(KeyCase::None),
+            e => Err(anyhow!("{} is an invalid key case", e)),
+        }
+    }
+}
+
 impl FromStr for FileFormat {
     type Err = anyhow::Error;
 
@@ -118,6 +200,7 @@ async fn main() {
     match cli.command {
         Commands::Import(args) => import(args).await.unwrap(),
         Commands::Export(args) => export(args).await.unwrap(),
+        Commands::Serve(args) => serve(args).await.unwrap(),
     }
 }
 
@@ -137,58 +220,217 @@ async fn connect_db(
 async fn import(args: Import) -> Result<()> {
     let db = connect_db(&args.endpoint, &args.user, &args.pass, &args.ns, &args.db).await?;
 
-    match file_format(&args.files)? {
+    let case = KeyCase::from_str(&args.rename_fields)?;
+    let preserve_ids = args.preserve_ids;
+    let (format, gzip) = file_format(&args.files)?;
+    match format {
         FileFormat::Json => {
             for path in args.files {
-                import_json(path, &db).await?;
+                import_json(path, gzip, case, preserve_ids, &db).await?;
+            }
+        }
+        FileFormat::Csv => {
+            for path in args.files {
+                import_csv(path, gzip, case, preserve_ids, &db).await?;
             }
         }
-        FileFormat::Csv => todo!(),
     }
     Ok(())
 }
 
-fn file_format(files: &Vec<PathBuf>) -> Result<FileFormat> {
+fn file_format(files: &[PathBuf]) -> Result<(FileFormat, bool)> {
     for path in files.iter() {
         if !path.is_file() {
             return Err(anyhow!("error: {:#?} is not a file", path));
         };
     }
-    let ext = files
-        .first()
-        .unwrap()
-        .extension()
-        .context("Error: no file extention was found")?;
 
-    if !files.iter().all(|p| p.extension().unwrap() == ext) {
-        return Err(anyhow!("error: not all files are the same type"));
+    // A trailing `.gz` marks compression; the real format lives in the inner
+    // extension, e.g. `data.csv.gz` -> csv + gzip.
+    let descriptor = |path: &PathBuf| -> Result<(std::ffi::OsString, bool)> {
+        let gzip = path.extension() == Some(OsStr::new("gz"));
+        let inner = if gzip {
+            PathBuf::from(path.file_stem().context("Error: no file extention was found")?)
+        } else {
+            path.clone()
+        };
+        let ext = inner
+            .extension()
+            .context("Error: no file extention was found")?
+            .to_owned();
+        Ok((ext, gzip))
+    };
+
+    let (ext, gzip) = descriptor(files.first().unwrap())?;
+    for path in files.iter() {
+        if descriptor(path)? != (ext.clone(), gzip) {
+            return Err(anyhow!("error: not all files are the same type"));
+        }
     }
 
-    FileFormat::try_from(ext)
+    Ok((FileFormat::try_from(ext.as_os_str())?, gzip))
 }
 
-async fn import_json(path: PathBuf, db: &Surreal<Client>) -> Result<()> {
-    let (insert_query, records) = generate_insert(path).await?;
-    let mut response = db.query(insert_query).bind(("records", &records)).await?;
-    assert!(response.take_errors().is_empty());
-    Ok(())
+// Read a source file to a string, transparently inflating it when `gzip` is set.
+async fn read_source(path: &PathBuf, gzip: bool) -> Result<String> {
+    if !gzip {
+        return Ok(fs::read_to_string(path).await?);
+    }
+    let file = fs::File::open(path).await?;
+    let mut decoder = GzipDecoder::new(BufReader::new(file));
+    let mut contents = String::new();
+    decoder.read_to_string(&mut contents).await?;
+    Ok(contents)
 }
 
-async fn generate_insert(path: PathBuf) -> Result<(String, Vec<Value>)> {
-    let records = {
-        let json = fs::read_to_string(&path).await?;
-        serde_json::from_str::<Vec<Value>>(&json)
-            .unwrap_or(vec![serde_json::from_str::<Value>(&json)?])
+// Derive the target table from a file name, ignoring a `.gz` suffix so both
+// `data.json` and `data.json.gz` map to the `data` table.
+fn table_name(path: &std::path::Path) -> Result<String> {
+    let path = if path.extension() == Some(OsStr::new("gz")) {
+        PathBuf::from(path.file_stem().context("failed to parse stem")?)
+    } else {
+        path.to_path_buf()
     };
-
-    let table = path
+    Ok(path
         .file_stem()
         .context("failed to parse stem")?
         .to_str()
-        .context("failed to convert &OsStr")?;
+        .context("failed to convert &OsStr")?
+        .to_owned())
+}
+
+async fn import_json(
+    path: PathBuf,
+    gzip: bool,
+    case: KeyCase,
+    preserve_ids: bool,
+    db: &Surreal<Client>,
+) -> Result<()> {
+    let (table, records) = generate_insert(path, gzip, case).await?;
+    insert_records(db, &table, records, preserve_ids).await
+}
+
+// Run `INSERT INTO type::table($table) $records`, binding the table name
+// rather than interpolating it so it can never be read as anything but a
+// table identifier. With `preserve_ids`, each record is converted into a typed
+// `sql::Value` first so that `table:id`-shaped strings bind as real record
+// links rather than plain strands.
+async fn insert_records(
+    db: &Surreal<Client>,
+    table: &str,
+    records: Vec<Value>,
+    preserve_ids: bool,
+) -> Result<()> {
+    let query = "INSERT INTO type::table($table) $records";
+    let mut response = if preserve_ids {
+        let records: Vec<sql::Value> = records
+            .into_iter()
+            .map(|record| json_to_sql(record, true))
+            .collect();
+        db.query(query)
+            .bind(("table", table.to_owned()))
+            .bind(("records", records))
+            .await?
+    } else {
+        db.query(query)
+            .bind(("table", table.to_owned()))
+            .bind(("records", records))
+            .await?
+    };
+    assert!(response.take_errors().is_empty());
+    Ok(())
+}
+
+async fn import_csv(
+    path: PathBuf,
+    gzip: bool,
+    case: KeyCase,
+    preserve_ids: bool,
+    db: &Surreal<Client>,
+) -> Result<()> {
+    let table = table_name(&path)?;
+
+    // A missing schema is not an error: fall back to string values below.
+    let types: Map<String, Value> = match extract_fields(&table, db).await {
+        Ok(fields) => fields
+            .into_iter()
+            .filter_map(|(field, ty)| ty.map(|ty| (field, Value::String(ty))))
+            .collect(),
+        Err(_) => Map::new(),
+    };
+
+    let csv = read_source(&path, gzip).await?;
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    let headers = reader.headers()?.clone();
+
+    let mut records = Vec::new();
+    for result in reader.records() {
+        let row = result?;
+        let mut record = Map::new();
+        for (field, cell) in headers.iter().zip(row.iter()) {
+            let ty = types.get(field).and_then(Value::as_str);
+            record.insert(field.to_owned(), coerce_cell(cell, ty));
+        }
+        let mut record = Value::Object(record);
+        rename_keys(&mut record, case);
+        records.push(record);
+    }
+
+    insert_records(db, &table, records, preserve_ids).await
+}
 
-    let query = format!("INSERT INTO {} $records", table);
-    Ok((query, records))
+// Coerce a raw CSV cell into the `Value` its declared column type expects so
+// that dumps produced by `export_table_as_csv` round-trip losslessly. Empty and
+// `NULL` cells always become `Value::Null`; columns with no declared type stay
+// strings.
+fn coerce_cell(cell: &str, ty: Option<&str>) -> Value {
+    if cell.is_empty() || cell == "NULL" {
+        return Value::Null;
+    }
+    match ty {
+        Some(ty) if ty.starts_with("array") || ty.starts_with("object") => {
+            serde_json::from_str(cell).unwrap_or_else(|_| Value::String(cell.to_owned()))
+        }
+        Some("int" | "float" | "decimal" | "number") => {
+            serde_json::from_str(cell).unwrap_or_else(|_| Value::String(cell.to_owned()))
+        }
+        Some("bool") => match cell {
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            _ => Value::String(cell.to_owned()),
+        },
+        _ => Value::String(cell.to_owned()),
+    }
+}
+
+async fn generate_insert(
+    path: PathBuf,
+    gzip: bool,
+    case: KeyCase,
+) -> Result<(String, Vec<Value>)> {
+    let mut records = parse_records(&read_source(&path, gzip).await?)?;
+    for record in &mut records {
+        rename_keys(record, case);
+    }
+
+    let table = table_name(&path)?;
+    Ok((table, records))
+}
+
+// Parse a record payload that may be a JSON array, a single JSON object, or
+// newline-delimited JSON (as emitted by the streaming exporter). The array and
+// single-object cases preserve the original CLI behaviour.
+fn parse_records(body: &str) -> Result<Vec<Value>> {
+    if let Ok(records) = serde_json::from_str::<Vec<Value>>(body) {
+        return Ok(records);
+    }
+    if let Ok(record) = serde_json::from_str::<Value>(body) {
+        return Ok(vec![record]);
+    }
+    body.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str::<Value>(line).map_err(Into::into))
+        .collect()
 }
 
 async fn export(args: Export) -> Result<()> {
@@ -199,42 +441,138 @@ async fn export(args: Export) -> Result<()> {
     for table in args.tables {
         let db = db.clone();
         let table = table.clone();
+        let batch_size = args.batch_size;
+        let pretty = args.pretty;
+        let compress = args.compress;
+        let case = KeyCase::from_str(&args.rename_fields)?;
+        let preserve_ids = args.preserve_ids;
         match format {
             FileFormat::Json => {
-                handles.spawn(async move { export_table_as_json(&table, &db).await });
+                handles.spawn(async move {
+                    export_table_as_json(&table, &db, batch_size, pretty, compress, case, preserve_ids)
+                        .await
+                });
             }
             FileFormat::Csv => {
-                handles.spawn(async move { export_table_as_csv(&table, &db).await });
+                handles.spawn(async move {
+                    export_table_as_csv(&table, &db, batch_size, compress, case, preserve_ids).await
+                });
             }
         }
     }
-    while let Some(_) = handles.join_next().await {}
+    while handles.join_next().await.is_some() {}
     Ok(())
 }
 
-async fn export_table_as_json(table: &str, db: &Surreal<Client>) -> Result<()> {
-    let file_name = PathBuf::from(format!("{}.json", table));
-    let records = select_table(table, db).await?;
+async fn export_table_as_json(
+    table: &str,
+    db: &Surreal<Client>,
+    batch_size: usize,
+    pretty: bool,
+    compress: bool,
+    case: KeyCase,
+    preserve_ids: bool,
+) -> Result<()> {
+    let extension = if compress { "json.gz" } else { "json" };
+    let file = fs::File::create(PathBuf::from(format!("{}.{}", table, extension))).await?;
+    // Inflate on read is transparent, so deflate on write is too: the gzip
+    // encoder is just another `AsyncWrite` sink in front of the file.
+    let mut file: Box<dyn AsyncWrite + Unpin + Send> = if compress {
+        Box::new(GzipEncoder::new(file))
+    } else {
+        Box::new(file)
+    };
+
+    // `--pretty`/`--array` keeps the legacy behaviour of buffering the whole
+    // table into a single pretty-printed JSON array.
+    if pretty {
+        let records = select_table(table, db, case, preserve_ids).await?;
+        let json = serde_json::to_string_pretty(&records)?;
+        file.write_all(json.as_bytes()).await?;
+        file.shutdown().await?;
+        return Ok(());
+    }
 
-    let json = serde_json::to_string_pretty(&records)?;
-    let mut file = fs::File::create(file_name).await?;
-    file.write_all(json.as_bytes()).await?;
+    // Default: page through the table and flush each record as NDJSON so large
+    // tables never have to live in memory all at once.
+    let mut offset = 0;
+    loop {
+        let batch = select_batch(table, db, batch_size, offset, case, preserve_ids).await?;
+        if batch.is_empty() {
+            break;
+        }
+        for record in &batch {
+            let mut line = serde_json::to_string(record)?;
+            line.push('\n');
+            file.write_all(line.as_bytes()).await?;
+        }
+        if batch.len() < batch_size {
+            break;
+        }
+        offset += batch_size;
+    }
+    file.shutdown().await?;
     Ok(())
 }
 
-// TODO add check to convert any record ids
-async fn select_table(table: &str, db: &Surreal<Client>) -> Result<Vec<Value>> {
-    let table: Vec<Value> = db.select(table).range(1..2).await?;
-    let table: Vec<Value> = table
+// Page the whole table through `select_batch` and concatenate the batches, so
+// the buffered `--pretty`/`--array` export and the HTTP export endpoint see
+// every record, not just a single page.
+async fn select_table(
+    table: &str,
+    db: &Surreal<Client>,
+    case: KeyCase,
+    preserve_ids: bool,
+) -> Result<Vec<Value>> {
+    let mut records = Vec::new();
+    let mut offset = 0;
+    loop {
+        let batch = select_batch(table, db, BATCH_SIZE, offset, case, preserve_ids).await?;
+        if batch.is_empty() {
+            break;
+        }
+        let done = batch.len() < BATCH_SIZE;
+        records.extend(batch);
+        if done {
+            break;
+        }
+        offset += BATCH_SIZE;
+    }
+    Ok(records)
+}
+
+// Page through a table one batch at a time. Ids are flattened here (or kept as
+// full `table:id` references with `preserve_ids`), and `select_table` pages
+// through this same function, so streaming and buffered exports produce
+// identical records.
+async fn select_batch(
+    table: &str,
+    db: &Surreal<Client>,
+    batch: usize,
+    offset: usize,
+    case: KeyCase,
+    preserve_ids: bool,
+) -> Result<Vec<Value>> {
+    let mut response = db
+        .query("SELECT * FROM type::table($table) LIMIT $batch START $offset")
+        .bind(("table", table.to_owned()))
+        .bind(("batch", batch))
+        .bind(("offset", offset))
+        .await?;
+    let records: Vec<Value> = response.take(0)?;
+    let records = records
         .into_iter()
         .map(|mut record| {
-            let Some(record) = convert_id(&mut record) else {
-                return record;
-            };
-            record.to_owned()
+            if preserve_ids {
+                preserve_things(&mut record);
+            } else {
+                convert_id(&mut record);
+            }
+            rename_keys(&mut record, case);
+            record
         })
         .collect();
-    Ok(table)
+    Ok(records)
 }
 
 fn convert_id(record: &mut Value) -> Option<&Value> {
@@ -244,36 +582,173 @@ fn convert_id(record: &mut Value) -> Option<&Value> {
     Some(record)
 }
 
-async fn export_table_as_csv(table: &str, db: &Surreal<Client>) -> Result<()> {
-    let records = select_table(table, db).await?;
+// Walk a record keeping full `table:id` references for the `id` field and every
+// nested `sql::Thing` (record link), so the graph can be reconstructed on
+// re-import with `--preserve-ids`.
+fn preserve_things(value: &mut Value) {
+    match value {
+        Value::Object(_) => {
+            if let Ok(thing) = serde_json::from_value::<sql::Thing>(value.clone()) {
+                *value = Value::String(thing.to_string());
+                return;
+            }
+            if let Value::Object(map) = value {
+                for field in map.values_mut() {
+                    preserve_things(field);
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                preserve_things(item);
+            }
+        }
+        _ => {}
+    }
+}
+
+// Convert a serde_json `Value` into a typed `sql::Value`, optionally promoting
+// `table:id`-shaped strings to record links. This mirrors SurrealDB's own
+// distinction between a plain strand and a `Thing` address.
+fn json_to_sql(value: Value, detect_links: bool) -> sql::Value {
+    match value {
+        Value::Null => sql::Value::Null,
+        Value::Bool(b) => sql::Value::Bool(b),
+        Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                sql::Value::Number(sql::Number::Int(i))
+            } else if let Some(f) = n.as_f64() {
+                sql::Value::Number(sql::Number::Float(f))
+            } else {
+                sql::Value::Null
+            }
+        }
+        Value::String(s) => match detect_links.then(|| parse_record_link(&s)).flatten() {
+            Some(thing) => sql::Value::Thing(thing),
+            None => sql::Value::Strand(s.into()),
+        },
+        Value::Array(items) => sql::Value::Array(sql::Array(
+            items.into_iter().map(|v| json_to_sql(v, detect_links)).collect(),
+        )),
+        Value::Object(map) => {
+            let object: BTreeMap<String, sql::Value> = map
+                .into_iter()
+                .map(|(key, val)| (key, json_to_sql(val, detect_links)))
+                .collect();
+            sql::Value::Object(sql::Object(object))
+        }
+    }
+}
+
+// Recognise an unambiguous `table:id` record link; anything else (including
+// strings that merely contain a colon) stays a plain value.
+fn parse_record_link(s: &str) -> Option<sql::Thing> {
+    let (table, id) = s.split_once(':')?;
+    if table.is_empty() || id.is_empty() {
+        return None;
+    }
+    if !table.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        return None;
+    }
+    sql::thing(s).ok()
+}
+
+async fn export_table_as_csv(
+    table: &str,
+    db: &Surreal<Client>,
+    batch_size: usize,
+    compress: bool,
+    case: KeyCase,
+    preserve_ids: bool,
+) -> Result<()> {
     let fields = extract_fields(table, db).await?;
-    let records = records_to_csv(&fields, records);
+    // Header names are renamed alongside the record keys so the columns still
+    // line up after a case transformation. `id` isn't a `DEFINE FIELD` so
+    // `extract_fields` never returns it; prepend it explicitly so the CSV
+    // carries the identifier needed to reconstruct records on re-import.
+    let mut fields: Vec<String> = fields
+        .into_iter()
+        .map(|(field, _)| convert_case(&field, case))
+        .collect();
+    fields.insert(0, convert_case("id", case));
 
-    let file_name = PathBuf::from(format!("{}.csv", table));
-    let file = std::fs::File::create(file_name)?;
-    let mut wtr = Writer::from_writer(file);
+    let extension = if compress { "csv.gz" } else { "csv" };
+    let file = std::fs::File::create(PathBuf::from(format!("{}.{}", table, extension)))?;
+    // The CSV writer is synchronous, so compression uses flate2's matching
+    // synchronous gzip encoder; the footer is written when `wtr` drops.
+    let sink: Box<dyn std::io::Write + Send> = if compress {
+        Box::new(flate2::write::GzEncoder::new(file, flate2::Compression::default()))
+    } else {
+        Box::new(file)
+    };
+    let mut wtr = Writer::from_writer(sink);
 
-    wtr.write_record(fields)?;
-    for record in records {
-        wtr.write_record(record)?;
+    // Header is written once up front, then rows are streamed batch by batch.
+    wtr.write_record(&fields)?;
+    let mut offset = 0;
+    loop {
+        let batch = select_batch(table, db, batch_size, offset, case, preserve_ids).await?;
+        if batch.is_empty() {
+            break;
+        }
+        let done = batch.len() < batch_size;
+        for record in records_to_csv(&fields, batch) {
+            wtr.write_record(record)?;
+        }
+        if done {
+            break;
+        }
+        offset += batch_size;
     }
     wtr.flush()?;
 
     Ok(())
 }
 
-async fn extract_fields(table: &str, db: &Surreal<Client>) -> Result<Vec<String>> {
+// `INFO FOR TABLE` doesn't accept a bound parameter in place of the table
+// identifier, so the name is validated as a plain identifier before it's
+// interpolated — this is the only thing standing between an HTTP caller and
+// query injection once `table` comes from a URL path segment.
+fn validate_table_name(table: &str) -> Result<()> {
+    if !table.is_empty() && table.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        Ok(())
+    } else {
+        Err(anyhow!("{} is not a valid table name", table))
+    }
+}
+
+async fn extract_fields(table: &str, db: &Surreal<Client>) -> Result<Vec<(String, Option<String>)>> {
+    validate_table_name(table)?;
     let mut response = db.query(format!("INFO FOR TABLE {}", table)).await?;
     let mut table = response
         .take::<Option<Value>>(0)?
         .context("failed to return table info")?;
 
     let fields = serde_json::from_value::<Map<String, Value>>(table["fields"].take())?;
-    let fields: Vec<String> = fields.into_iter().map(|(field, _)| field).collect();
+    let fields = fields
+        .into_iter()
+        .map(|(field, def)| {
+            let ty = def.as_str().and_then(parse_field_type);
+            (field, ty)
+        })
+        .collect();
     Ok(fields)
 }
 
-fn records_to_csv(fields: &Vec<String>, records: Vec<Value>) -> Vec<Vec<String>> {
+// The `fields` map from `INFO FOR TABLE` holds each field's `DEFINE FIELD`
+// statement, e.g. `DEFINE FIELD name ON pokemon TYPE string`; pull the declared
+// type out of it so CSV cells can be coerced back to the right `Value`.
+fn parse_field_type(definition: &str) -> Option<String> {
+    let mut tokens = definition.split_whitespace();
+    while let Some(token) = tokens.next() {
+        if token == "TYPE" {
+            return tokens.next().map(str::to_owned);
+        }
+    }
+    None
+}
+
+fn records_to_csv(fields: &[String], records: Vec<Value>) -> Vec<Vec<String>> {
     let format_record = |rec: Value| {
         fields
             .iter()
@@ -290,6 +765,71 @@ fn records_to_csv(fields: &Vec<String>, records: Vec<Value>) -> Vec<Vec<String>>
     records.into_iter().map(format_record).collect()
 }
 
+// Recursively rewrite every object key in a `Value` (including keys nested in
+// arrays) to the chosen casing, so a whole payload can be normalised in one
+// pass. `None` is a no-op.
+fn rename_keys(value: &mut Value, case: KeyCase) {
+    if let KeyCase::None = case {
+        return;
+    }
+    match value {
+        Value::Object(map) => {
+            *map = std::mem::take(map)
+                .into_iter()
+                .map(|(key, mut val)| {
+                    rename_keys(&mut val, case);
+                    (convert_case(&key, case), val)
+                })
+                .collect();
+        }
+        Value::Array(items) => {
+            for item in items {
+                rename_keys(item, case);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn convert_case(key: &str, case: KeyCase) -> String {
+    match case {
+        KeyCase::Snake => to_snake_case(key),
+        KeyCase::Camel => to_camel_case(key),
+        KeyCase::None => key.to_owned(),
+    }
+}
+
+fn to_snake_case(key: &str) -> String {
+    let mut out = String::with_capacity(key.len() + 4);
+    for (i, ch) in key.chars().enumerate() {
+        if ch.is_uppercase() {
+            if i != 0 && !out.ends_with('_') {
+                out.push('_');
+            }
+            out.extend(ch.to_lowercase());
+        } else {
+            out.push(ch);
+        }
+    }
+    out
+}
+
+fn to_camel_case(key: &str) -> String {
+    let mut out = String::with_capacity(key.len());
+    let mut upper_next = false;
+    for ch in key.chars() {
+        if ch == '_' {
+            upper_next = true;
+        } else if upper_next {
+            out.extend(ch.to_uppercase());
+            upper_next = false;
+        } else {
+            out.push(ch);
+        }
+    }
+    out
+}
+
 fn value_to_string(val: &Value) -> String {
     match val {
         Value::Null => String::from("NULL"),
@@ -301,6 +841,210 @@ fn value_to_string(val: &Value) -> String {
     }
 }
 
+// Shared axum state: the `Surreal` client plus the table of import jobs that
+// `import_progress` polls. `Surreal<Client>` is itself a cheap
+// `Clone`, so cloning `AppState` into each handler is free.
+#[derive(Clone)]
+struct AppState {
+    db: Surreal<Client>,
+    jobs: ImportJobs,
+}
+
+// Tracks the latest `ImportJob` per table imported via `POST /import/:table`,
+// so `GET /import/:table` can report real progress on it. Jobs are never
+// removed: a finished job stays until the next import of the same table.
+#[derive(Clone, Default)]
+struct ImportJobs(Arc<Mutex<HashMap<String, Arc<ImportJob>>>>);
+
+struct ImportJob {
+    total: usize,
+    processed: AtomicUsize,
+    done: AtomicBool,
+    error: Mutex<Option<String>>,
+}
+
+impl ImportJobs {
+    fn start(&self, table: &str, total: usize) -> Arc<ImportJob> {
+        let job = Arc::new(ImportJob {
+            total,
+            processed: AtomicUsize::new(0),
+            done: AtomicBool::new(false),
+            error: Mutex::new(None),
+        });
+        self.0.lock().unwrap().insert(table.to_owned(), job.clone());
+        job
+    }
+
+    fn get(&self, table: &str) -> Option<Arc<ImportJob>> {
+        self.0.lock().unwrap().get(table).cloned()
+    }
+}
+
+// Wrap the core transfer logic in an HTTP service so it can be driven by
+// browser clients and other tools without reimplementing import/export. The
+// `Surreal` client and the import job table are shared across handlers as
+// axum state.
+async fn serve(args: Serve) -> Result<()> {
+    let db = connect_db(&args.endpoint, &args.user, &args.pass, &args.ns, &args.db).await?;
+    let state = AppState {
+        db,
+        jobs: ImportJobs::default(),
+    };
+
+    let app = Router::new()
+        .route("/import/:table", post(import_records).get(import_progress))
+        .route("/export/:table", get(export_records))
+        .layer(CompressionLayer::new())
+        .layer(CorsLayer::permissive())
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(&args.bind).await?;
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+// `POST /import/:table` — accepts a JSON array, a single object, or NDJSON
+// body and inserts it in `BATCH_SIZE` chunks with the same `INSERT` query as
+// the CLI `import` path (which sends each file as one query), tracking a job
+// in `state.jobs` that `GET /import/:table` can poll.
+async fn import_records(
+    State(state): State<AppState>,
+    Path(table): Path<String>,
+    body: String,
+) -> Response {
+    if let Err(e) = validate_table_name(&table) {
+        return (StatusCode::BAD_REQUEST, e.to_string()).into_response();
+    }
+
+    let records = match parse_records(&body) {
+        Ok(records) => records,
+        Err(e) => return (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    };
+
+    let job = state.jobs.start(&table, records.len());
+    // The table comes straight from the URL path, so it is bound rather than
+    // interpolated into the query, exactly like `select_batch` binds it for reads.
+    let query = "INSERT INTO type::table($table) $records";
+    for chunk in records.chunks(BATCH_SIZE) {
+        let result = state
+            .db
+            .query(query)
+            .bind(("table", table.clone()))
+            .bind(("records", chunk.to_vec()))
+            .await;
+        match result {
+            Ok(mut response) => {
+                let errors = response.take_errors();
+                if !errors.is_empty() {
+                    let msg = format!("{:?}", errors);
+                    *job.error.lock().unwrap() = Some(msg.clone());
+                    job.done.store(true, Ordering::SeqCst);
+                    return (StatusCode::BAD_REQUEST, msg).into_response();
+                }
+            }
+            Err(e) => {
+                let msg = e.to_string();
+                *job.error.lock().unwrap() = Some(msg.clone());
+                job.done.store(true, Ordering::SeqCst);
+                return (StatusCode::INTERNAL_SERVER_ERROR, msg).into_response();
+            }
+        }
+        job.processed.fetch_add(chunk.len(), Ordering::SeqCst);
+    }
+    job.done.store(true, Ordering::SeqCst);
+    let processed = job.processed.load(Ordering::SeqCst);
+    (StatusCode::OK, format!("inserted {} records", processed)).into_response()
+}
+
+// `GET /export/:table?format=json|csv` — returns the serialized table in one
+// response.
+async fn export_records(
+    State(state): State<AppState>,
+    Path(table): Path<String>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Response {
+    if let Err(e) = validate_table_name(&table) {
+        return (StatusCode::BAD_REQUEST, e.to_string()).into_response();
+    }
+    let db = &state.db;
+
+    let records = match select_table(&table, db, KeyCase::None, false).await {
+        Ok(records) => records,
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    };
+
+    match params.get("format").map(String::as_str) {
+        Some("csv") => {
+            let fields = match extract_fields(&table, db).await {
+                Ok(fields) => {
+                    let mut fields: Vec<String> =
+                        fields.into_iter().map(|(field, _)| field).collect();
+                    fields.insert(0, "id".to_owned());
+                    fields
+                }
+                Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+            };
+            let mut wtr = Writer::from_writer(Vec::new());
+            let write = || -> Result<Vec<u8>> {
+                wtr.write_record(&fields)?;
+                for record in records_to_csv(&fields, records) {
+                    wtr.write_record(record)?;
+                }
+                Ok(wtr.into_inner()?)
+            };
+            match write() {
+                Ok(bytes) => ([(header::CONTENT_TYPE, "text/csv")], bytes).into_response(),
+                Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+            }
+        }
+        _ => match serde_json::to_string_pretty(&records) {
+            Ok(json) => ([(header::CONTENT_TYPE, "application/json")], json).into_response(),
+            Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+        },
+    }
+}
+
+// How often `import_progress` re-checks the job while it's still running.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+// `GET /import/:table` (SSE) — polls the `ImportJob` that `POST /import/:table`
+// is updating and emits a `progress` event per poll, then a final `complete`
+// (or `error`) event once the import finishes, keeping the connection warm
+// for the duration of the job.
+async fn import_progress(
+    State(state): State<AppState>,
+    Path(table): Path<String>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let stream = futures::stream::unfold((state, table, false), |(state, table, done)| async move {
+        if done {
+            return None;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+
+        let Some(job) = state.jobs.get(&table) else {
+            let data = serde_json::json!({ "waiting": true }).to_string();
+            return Some((Ok(Event::default().event("waiting").data(data)), (state, table, false)));
+        };
+
+        let processed = job.processed.load(Ordering::SeqCst);
+        let total = job.total;
+        if !job.done.load(Ordering::SeqCst) {
+            let data = serde_json::json!({ "processed": processed, "total": total }).to_string();
+            return Some((Ok(Event::default().event("progress").data(data)), (state, table, false)));
+        }
+
+        let error = job.error.lock().unwrap().clone();
+        if let Some(error) = error {
+            Some((Ok(Event::default().event("error").data(error)), (state, table, true)))
+        } else {
+            let data = serde_json::json!({ "processed": processed, "total": total }).to_string();
+            Some((Ok(Event::default().event("complete").data(data)), (state, table, true)))
+        }
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -345,13 +1089,137 @@ mod tests {
     #[tokio::test]
     async fn test_export() {
         let db = connect_db(ENDPOINT, USER, PASS, NS, DB).await.unwrap();
-        let x = select_table("pokemon", &db).await.unwrap();
+        let x = select_table("pokemon", &db, KeyCase::None, false)
+            .await
+            .unwrap();
         println!("{:?}", x)
     }
 
     #[tokio::test]
     async fn test_table_save() {
         let db = connect_db(ENDPOINT, USER, PASS, NS, DB).await.unwrap();
-        export_table_as_json("empty_pokemon", &db).await.unwrap();
+        export_table_as_json("empty_pokemon", &db, BATCH_SIZE, true, false, KeyCase::None, false)
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn snake_case_inserts_underscore_before_each_uppercase_letter() {
+        assert_eq!(to_snake_case("helloWorld"), "hello_world");
+        assert_eq!(to_snake_case("HelloWorld"), "hello_world");
+        assert_eq!(to_snake_case("already_snake"), "already_snake");
+        assert_eq!(to_snake_case("id"), "id");
+    }
+
+    #[test]
+    fn camel_case_capitalises_the_letter_after_each_underscore() {
+        assert_eq!(to_camel_case("hello_world"), "helloWorld");
+        assert_eq!(to_camel_case("already_camel"), "alreadyCamel");
+        assert_eq!(to_camel_case("id"), "id");
+    }
+
+    #[test]
+    fn camel_and_snake_case_round_trip() {
+        let camel = "pokemonId";
+        assert_eq!(to_camel_case(&to_snake_case(camel)), camel);
+    }
+
+    #[test]
+    fn rename_keys_rewrites_object_keys_recursively_and_in_arrays() {
+        let mut value = serde_json::json!({
+            "pokemonName": "Pikachu",
+            "stats": [{"attackPower": 55}],
+        });
+        rename_keys(&mut value, KeyCase::Snake);
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "pokemon_name": "Pikachu",
+                "stats": [{"attack_power": 55}],
+            })
+        );
+    }
+
+    #[test]
+    fn rename_keys_is_a_no_op_for_key_case_none() {
+        let mut value = serde_json::json!({"pokemonName": "Pikachu"});
+        let original = value.clone();
+        rename_keys(&mut value, KeyCase::None);
+        assert_eq!(value, original);
+    }
+
+    #[test]
+    fn coerce_cell_treats_empty_and_null_cells_as_null() {
+        assert_eq!(coerce_cell("", Some("int")), Value::Null);
+        assert_eq!(coerce_cell("NULL", None), Value::Null);
+    }
+
+    #[test]
+    fn coerce_cell_parses_numbers_and_bools_by_declared_type() {
+        assert_eq!(coerce_cell("5", Some("int")), serde_json::json!(5));
+        assert_eq!(coerce_cell("true", Some("bool")), Value::Bool(true));
+        assert_eq!(coerce_cell("false", Some("bool")), Value::Bool(false));
+    }
+
+    #[test]
+    fn coerce_cell_falls_back_to_a_string_without_a_declared_type() {
+        assert_eq!(coerce_cell("Pikachu", None), Value::String("Pikachu".to_owned()));
+    }
+
+    #[test]
+    fn parse_field_type_reads_the_type_token_from_a_define_field_statement() {
+        assert_eq!(
+            parse_field_type("DEFINE FIELD name ON pokemon TYPE string"),
+            Some("string".to_owned())
+        );
+        assert_eq!(parse_field_type("DEFINE FIELD name ON pokemon"), None);
+    }
+
+    #[test]
+    fn parse_record_link_recognises_table_colon_id_but_not_plain_strings() {
+        assert!(parse_record_link("pokemon:1").is_some());
+        assert_eq!(parse_record_link("not a link"), None);
+        assert_eq!(parse_record_link("http://example.com"), None);
+    }
+
+    #[test]
+    fn json_to_sql_promotes_record_links_only_when_detecting() {
+        let value = Value::String("pokemon:1".to_owned());
+        assert!(matches!(json_to_sql(value.clone(), true), sql::Value::Thing(_)));
+        assert!(matches!(json_to_sql(value, false), sql::Value::Strand(_)));
+    }
+
+    #[test]
+    fn preserve_things_stringifies_nested_thing_objects() {
+        // `sql::Thing`'s own (de)serialization shape: `{tb, id: {Number|String: ..}}`.
+        let mut value = serde_json::json!({
+            "id": {"tb": "pokemon", "id": {"Number": 1}},
+            "trainer": {"tb": "trainer", "id": {"Number": 2}},
+            "nickname": "Sparky",
+        });
+        preserve_things(&mut value);
+        assert_eq!(value["id"], serde_json::json!("pokemon:1"));
+        assert_eq!(value["trainer"], serde_json::json!("trainer:2"));
+        assert_eq!(value["nickname"], serde_json::json!("Sparky"));
+    }
+
+    #[test]
+    fn file_format_recognises_a_trailing_gz_as_compression() {
+        let dir = std::env::temp_dir().join("surrealdb_json_cli_file_format_test");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let plain = dir.join("pokemon.csv");
+        std::fs::write(&plain, "").unwrap();
+        let (format, gzip) = file_format(std::slice::from_ref(&plain)).unwrap();
+        assert!(matches!(format, FileFormat::Csv));
+        assert!(!gzip);
+
+        let compressed = dir.join("pokemon.json.gz");
+        std::fs::write(&compressed, "").unwrap();
+        let (format, gzip) = file_format(std::slice::from_ref(&compressed)).unwrap();
+        assert!(matches!(format, FileFormat::Json));
+        assert!(gzip);
+
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 }